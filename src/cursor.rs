@@ -0,0 +1,213 @@
+//! Lightweight, borrowing cursors over a [`VoodooBuffer`], for callers who want
+//! wrap-aware bulk copies without going through the owning [`VoodooCursor`].
+
+use std::ops::{ControlFlow, Range};
+use std::ptr;
+
+use crate::VoodooBuffer;
+
+/// A borrowing, read-only cursor over a [`VoodooBuffer`].
+pub struct BufferCursor<'a> {
+    buf: &'a VoodooBuffer,
+    pos: usize,
+}
+
+/// A borrowing, read-write cursor over a [`VoodooBuffer`].
+pub struct BufferCursorMut<'a> {
+    buf: &'a mut VoodooBuffer,
+    pos: usize,
+}
+
+/// Shared implementation of [`BufferCursor::read_into`]/[`BufferCursorMut::read_into`];
+/// both only ever need a shared `&VoodooBuffer`, so they delegate here instead of
+/// maintaining two copies of the same wrap-aware copy.
+fn read_into(buf: &VoodooBuffer, pos: &mut usize, dst: &mut [u8]) -> usize {
+    let len = dst.len().min(buf.len());
+    let offset = buf.fast_mod(*pos);
+
+    // Safety: `len` is bounded by `buf.len()`, so the source region never aliases
+    // itself across the wrap point.
+    unsafe {
+        let src = buf.as_slice(offset, len);
+        ptr::copy_nonoverlapping(src.as_ptr(), dst.as_mut_ptr(), len);
+    }
+
+    *pos += len;
+    len
+}
+
+/// Shared implementation of [`BufferCursor::for_each_chunk`]/
+/// [`BufferCursorMut::for_each_chunk`]; both only ever need a shared `&VoodooBuffer`,
+/// so they delegate here instead of maintaining two copies of the same chunk loop.
+fn for_each_chunk<F>(buf: &VoodooBuffer, range: Range<usize>, mut f: F) -> ControlFlow<()>
+where
+    F: FnMut(&[u8]) -> ControlFlow<()>,
+{
+    if range.start > range.end {
+        return ControlFlow::Continue(());
+    }
+
+    let mut pos = range.start;
+    while pos < range.end {
+        let len = (range.end - pos).min(buf.len());
+        let offset = buf.fast_mod(pos);
+
+        // Safety: `len` is bounded by `buf.len()`, so the chunk never aliases itself
+        // across the wrap point.
+        let chunk = unsafe { buf.as_slice(offset, len) };
+        f(chunk)?;
+
+        pos += len;
+    }
+
+    ControlFlow::Continue(())
+}
+
+impl<'a> BufferCursor<'a> {
+    /// Creates a cursor over `buf`, starting at position 0.
+    pub fn new(buf: &'a VoodooBuffer) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    /// Current position of the cursor, counted without wrapping.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Moves the cursor to an absolute position.
+    pub fn seek(&mut self, pos: usize) {
+        self.pos = pos;
+    }
+
+    /// Copies `min(dst.len(), buf.len())` bytes from the current position into `dst`,
+    /// advancing the cursor. Because the buffer is double-mapped, this is always a
+    /// single `copy_nonoverlapping`, even when the region straddles the wrap point.
+    pub fn read_into(&mut self, dst: &mut [u8]) -> usize {
+        read_into(self.buf, &mut self.pos, dst)
+    }
+
+    /// Calls `f` once per contiguous chunk of `range`, short-circuiting as soon as `f`
+    /// returns [`ControlFlow::Break`]. The mirror mapping means any sub-range up to
+    /// `buf.len()` long is handed to `f` as a single chunk, even across the wrap point;
+    /// a `range` longer than `buf.len()` is decomposed into consecutive chunks of at most
+    /// `buf.len()` bytes each.
+    pub fn for_each_chunk<F>(&self, range: Range<usize>, f: F) -> ControlFlow<()>
+    where
+        F: FnMut(&[u8]) -> ControlFlow<()>,
+    {
+        for_each_chunk(self.buf, range, f)
+    }
+}
+
+impl<'a> BufferCursorMut<'a> {
+    /// Creates a cursor over `buf`, starting at position 0.
+    pub fn new(buf: &'a mut VoodooBuffer) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    /// Current position of the cursor, counted without wrapping.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Moves the cursor to an absolute position.
+    pub fn seek(&mut self, pos: usize) {
+        self.pos = pos;
+    }
+
+    /// Copies `min(dst.len(), buf.len())` bytes from the current position into `dst`,
+    /// advancing the cursor. Because the buffer is double-mapped, this is always a
+    /// single `copy_nonoverlapping`, even when the region straddles the wrap point.
+    pub fn read_into(&mut self, dst: &mut [u8]) -> usize {
+        read_into(self.buf, &mut self.pos, dst)
+    }
+
+    /// Copies `min(src.len(), buf.len())` bytes from `src` into the current position,
+    /// advancing the cursor. Because the buffer is double-mapped, this is always a
+    /// single `copy_nonoverlapping`, even when the region straddles the wrap point.
+    pub fn write_from(&mut self, src: &[u8]) -> usize {
+        let len = src.len().min(self.buf.len());
+        let offset = self.buf.fast_mod(self.pos);
+
+        // Safety: `len` is bounded by `self.buf.len()`, so the destination region never
+        // aliases itself across the wrap point.
+        unsafe {
+            let dst = self.buf.as_slice_mut(offset, len);
+            ptr::copy_nonoverlapping(src.as_ptr(), dst.as_mut_ptr(), len);
+        }
+
+        self.pos += len;
+        len
+    }
+
+    /// Calls `f` once per contiguous chunk of `range`, short-circuiting as soon as `f`
+    /// returns [`ControlFlow::Break`]. The mirror mapping means any sub-range up to
+    /// `buf.len()` long is handed to `f` as a single chunk, even across the wrap point;
+    /// a `range` longer than `buf.len()` is decomposed into consecutive chunks of at most
+    /// `buf.len()` bytes each.
+    pub fn for_each_chunk<F>(&self, range: Range<usize>, f: F) -> ControlFlow<()>
+    where
+        F: FnMut(&[u8]) -> ControlFlow<()>,
+    {
+        for_each_chunk(self.buf, range, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID_BUF_LEN: usize = 1 << 16;
+
+    #[test]
+    fn write_from_then_read_into_round_trips() {
+        let mut buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let mut cursor = BufferCursorMut::new(&mut buf);
+
+        let written = cursor.write_from(b"hello");
+        assert_eq!(written, 5);
+
+        cursor.seek(0);
+        let mut out = [0u8; 5];
+        let read = cursor.read_into(&mut out);
+        assert_eq!(read, 5);
+        assert_eq!(&out, b"hello");
+    }
+
+    #[test]
+    fn for_each_chunk_sees_a_single_contiguous_view_across_wrap() {
+        let mut buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
+        {
+            let mut writer = BufferCursorMut::new(&mut buf);
+            writer.seek(VALID_BUF_LEN - 2);
+            writer.write_from(b"wrap");
+        }
+
+        let cursor = BufferCursor::new(&buf);
+        let mut seen = 0;
+        let _ = cursor.for_each_chunk(VALID_BUF_LEN - 2..VALID_BUF_LEN + 2, |chunk| {
+            seen = chunk.len();
+            assert_eq!(chunk, b"wrap");
+            ControlFlow::Break(())
+        });
+
+        assert_eq!(seen, 4);
+    }
+
+    #[test]
+    fn for_each_chunk_decomposes_oversized_ranges_instead_of_dropping_them() {
+        let buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let cursor = BufferCursor::new(&buf);
+
+        let mut visited = 0;
+        let mut chunks = 0;
+        let _ = cursor.for_each_chunk(0..VALID_BUF_LEN * 2, |chunk| {
+            visited += chunk.len();
+            chunks += 1;
+            ControlFlow::Continue(())
+        });
+
+        assert_eq!(visited, VALID_BUF_LEN * 2);
+        assert_eq!(chunks, 2);
+    }
+}
@@ -0,0 +1,171 @@
+//! `std::io` integration: a cursor over a [`VoodooBuffer`] that treats the whole buffer
+//! as a fixed-capacity, always-wrapping stream.
+
+use std::io::{self, BufRead, Read, Seek, SeekFrom, Write};
+
+use crate::VoodooBuffer;
+
+/// A [`std::io::Read`]/[`Write`]/[`BufRead`]/[`Seek`] cursor over a [`VoodooBuffer`].
+///
+/// Unlike [`std::io::Cursor`], reads and writes never have to special-case the wrap
+/// point: because the buffer is double-mapped, [`fill_buf`](BufRead::fill_buf) can hand
+/// back a single contiguous slice even when the logical region straddles the end of the
+/// buffer.
+#[derive(Debug)]
+pub struct VoodooCursor {
+    buf: VoodooBuffer,
+    pos: usize,
+    written: usize,
+}
+
+impl VoodooCursor {
+    /// Wraps `buf` in a cursor starting at position 0.
+    pub fn new(buf: VoodooBuffer) -> Self {
+        Self {
+            buf,
+            pos: 0,
+            written: 0,
+        }
+    }
+
+    /// Current position of the cursor, counted without wrapping.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Unwraps the cursor, returning the underlying buffer.
+    pub fn into_inner(self) -> VoodooBuffer {
+        self.buf
+    }
+}
+
+impl Read for VoodooCursor {
+    /// Reads up to `min(buf.len(), self.buf.len())` bytes, but never past the
+    /// high-water mark left by prior [`write`](Write::write) calls: once the cursor's
+    /// position catches up to everything that has ever been written, this returns
+    /// `Ok(0)` like any other `Read` source at EOF, instead of handing back stale
+    /// zeroed memory forever.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.written.saturating_sub(self.pos);
+        let len = buf.len().min(self.buf.len()).min(available);
+        if len == 0 {
+            return Ok(0);
+        }
+        let offset = self.buf.fast_mod(self.pos);
+
+        // Safety: `len` is bounded by `self.buf.len()`, so the slice never aliases
+        // itself across the wrap point.
+        buf[..len].copy_from_slice(unsafe { self.buf.as_slice(offset, len) });
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+impl Write for VoodooCursor {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.buf.len());
+        let offset = self.buf.fast_mod(self.pos);
+
+        // Safety: `len` is bounded by `self.buf.len()`, so the slice never aliases
+        // itself across the wrap point.
+        unsafe { self.buf.as_slice_mut(offset, len) }.copy_from_slice(&buf[..len]);
+        self.pos += len;
+        self.written = self.written.max(self.pos);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl BufRead for VoodooCursor {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let offset = self.buf.fast_mod(self.pos);
+
+        // Safety: `self.buf.len()` bytes are always valid starting at any offset,
+        // because the mirror mapping makes every byte reachable contiguously.
+        Ok(unsafe { self.buf.as_slice(offset, self.buf.len()) })
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos += amt;
+    }
+}
+
+impl Seek for VoodooCursor {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(n) => n as i64,
+            SeekFrom::End(n) => self.buf.len() as i64 + n,
+            SeekFrom::Current(n) => self.pos as i64 + n,
+        };
+
+        if new_pos < 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative position",
+            ));
+        }
+
+        self.pos = new_pos as usize;
+        Ok(self.pos as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID_BUF_LEN: usize = 1 << 16;
+
+    #[test]
+    fn write_then_read_round_trips() {
+        let buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let mut cursor = VoodooCursor::new(buf);
+
+        cursor.write_all(b"hello").expect("should write");
+        cursor.seek(SeekFrom::Start(0)).expect("should seek");
+
+        let mut out = [0u8; 5];
+        cursor.read_exact(&mut out).expect("should read");
+        assert_eq!(&out, b"hello");
+    }
+
+    #[test]
+    fn read_signals_eof_past_what_was_written() {
+        let buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let mut cursor = VoodooCursor::new(buf);
+
+        let mut out = Vec::new();
+        let read = cursor.read_to_end(&mut out).expect("should read to end");
+        assert_eq!(read, 0);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn read_stops_at_write_high_water_mark() {
+        let buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let mut cursor = VoodooCursor::new(buf);
+
+        cursor.write_all(b"hello").expect("should write");
+        cursor.seek(SeekFrom::Start(0)).expect("should seek");
+
+        let mut out = Vec::new();
+        let read = cursor.read_to_end(&mut out).expect("should read to end");
+        assert_eq!(read, 5);
+        assert_eq!(out, b"hello");
+    }
+
+    #[test]
+    fn fill_buf_is_contiguous_across_wrap() {
+        let buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let mut cursor = VoodooCursor::new(buf);
+
+        cursor
+            .seek(SeekFrom::Start((VALID_BUF_LEN - 2) as u64))
+            .expect("should seek");
+        let available = cursor.fill_buf().expect("should fill buf");
+        assert_eq!(available.len(), VALID_BUF_LEN);
+    }
+}
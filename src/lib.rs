@@ -1,12 +1,40 @@
 use std::{
     error::Error,
     fmt::{Display, Formatter},
+    mem::size_of,
     ops::{
-        Deref, DerefMut, Index, IndexMut, Range, RangeFrom, RangeFull, RangeTo, RangeToInclusive,
+        Bound, Deref, DerefMut, Index, IndexMut, Range, RangeBounds, RangeFrom, RangeFull,
+        RangeInclusive, RangeTo, RangeToInclusive,
     },
     ptr::{slice_from_raw_parts, slice_from_raw_parts_mut},
 };
 
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for super::Range<usize> {}
+    impl Sealed for super::RangeFrom<usize> {}
+    impl Sealed for super::RangeFull {}
+    impl Sealed for super::RangeInclusive<usize> {}
+    impl Sealed for super::RangeTo<usize> {}
+    impl Sealed for super::RangeToInclusive<usize> {}
+}
+
+/// The concrete range types `VoodooBuffer` can be indexed by.
+///
+/// This is sealed (closed over a fixed set of `std` range types) rather than a blanket
+/// `RangeBounds<usize>` bound, so that `Index<usize>` and `Index<R>` can coexist:
+/// coherence can't rule out an upstream `impl RangeBounds<usize> for usize`, but it can
+/// see that nothing outside this crate can implement `Sealed`.
+pub trait BufferRange: RangeBounds<usize> + sealed::Sealed {}
+
+impl BufferRange for Range<usize> {}
+impl BufferRange for RangeFrom<usize> {}
+impl BufferRange for RangeFull {}
+impl BufferRange for RangeInclusive<usize> {}
+impl BufferRange for RangeTo<usize> {}
+impl BufferRange for RangeToInclusive<usize> {}
+
 #[cfg(target_family = "windows")]
 mod windows;
 
@@ -25,6 +53,15 @@ mod macos;
 #[cfg(any(target_os = "macos", target_os = "ios"))]
 use macos::*;
 
+mod ring;
+pub use ring::{Consumer, Producer};
+
+mod io;
+pub use io::VoodooCursor;
+
+mod cursor;
+pub use cursor::{BufferCursor, BufferCursorMut};
+
 #[derive(Debug)]
 pub struct BufferError {
     msg: String,
@@ -43,14 +80,19 @@ impl Error for BufferError {
 }
 
 #[derive(Debug)]
-pub struct VoodooBuffer {
-    addr: *mut u8,
+pub struct VoodooBuffer<T = u8> {
+    addr: *mut T,
     len: usize,
     mask: usize,
 }
 
 #[allow(clippy::len_without_is_empty)]
-impl VoodooBuffer {
+impl<T: Copy> VoodooBuffer<T> {
+    /// Allocates a ring of `len` elements of `T`, double-mapped so that element `i` and
+    /// element `i + len` alias the same physical page.
+    ///
+    /// `len` is a count of `T`, not bytes; the underlying byte length (`len *
+    /// size_of::<T>()`) must still be page aligned.
     pub fn new(len: usize) -> Result<Self, BufferError> {
         if len == 0 {
             return Err(BufferError {
@@ -64,20 +106,30 @@ impl VoodooBuffer {
             });
         }
 
+        let byte_len = len.checked_mul(size_of::<T>()).ok_or_else(|| BufferError {
+            msg: "len * size_of::<T>() overflows usize".to_string(),
+        })?;
         let min_len = Self::min_len();
-        if len % min_len != 0 {
+        if byte_len % min_len != 0 {
             return Err(BufferError {
                 msg: format!("len must be page aligned, {}", min_len),
             });
         }
 
         Ok(Self {
-            addr: unsafe { voodoo_buf_alloc(len) }?,
+            addr: unsafe { voodoo_buf_alloc(byte_len) }? as *mut T,
             mask: len - 1,
             len,
         })
     }
 
+    /// Initializes a new buffer from an existing slice, copying its contents in.
+    pub fn from_slice(data: &[T]) -> Result<Self, BufferError> {
+        let mut buf = Self::new(data.len())?;
+        unsafe { buf.as_slice_mut(0, data.len()) }.copy_from_slice(data);
+        Ok(buf)
+    }
+
     pub fn min_len() -> usize {
         unsafe { voodoo_buf_min_len() }
     }
@@ -87,144 +139,116 @@ impl VoodooBuffer {
     }
 
     #[inline(always)]
-    unsafe fn as_slice(&self, offset: usize, len: usize) -> &[u8] {
+    pub(crate) unsafe fn as_slice(&self, offset: usize, len: usize) -> &[T] {
         &*(slice_from_raw_parts(self.addr.add(offset), len))
     }
 
     #[inline(always)]
-    unsafe fn as_slice_mut(&mut self, offset: usize, len: usize) -> &mut [u8] {
+    pub(crate) unsafe fn as_slice_mut(&mut self, offset: usize, len: usize) -> &mut [T] {
         &mut *(slice_from_raw_parts_mut(self.addr.add(offset), len))
     }
 
     #[inline(always)]
-    fn fast_mod(&self, v: usize) -> usize {
+    pub(crate) fn fast_mod(&self, v: usize) -> usize {
         v & self.mask
     }
+
+    /// Normalizes any `RangeBounds<usize>` into a `[start, end)` pair.
+    fn resolve_range<R: RangeBounds<usize>>(&self, range: &R) -> (usize, usize) {
+        let start = match range.start_bound() {
+            Bound::Included(&s) => s,
+            Bound::Excluded(&s) => s + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&e) => e + 1,
+            Bound::Excluded(&e) => e,
+            Bound::Unbounded => self.len,
+        };
+
+        (start, end)
+    }
+
+    /// Like [`as_slice_mut`](Self::as_slice_mut), but callable through a shared
+    /// reference.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure no other live reference aliases the returned slice, e.g.
+    /// by only ever using this to hand out disjoint regions, as [`split`](Self::split)
+    /// does for its producer/consumer halves.
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    pub(crate) unsafe fn as_slice_mut_shared(&self, offset: usize, len: usize) -> &mut [T] {
+        &mut *(slice_from_raw_parts_mut(self.addr.add(offset), len))
+    }
 }
 
-impl Drop for VoodooBuffer {
+impl<T> Drop for VoodooBuffer<T> {
     fn drop(&mut self) {
-        unsafe { voodoo_buf_free(self.addr, self.len) }
+        unsafe { voodoo_buf_free(self.addr as *mut u8, self.len * size_of::<T>()) }
     }
 }
 
-impl Deref for VoodooBuffer {
-    type Target = [u8];
+impl<T: Copy> Deref for VoodooBuffer<T> {
+    type Target = [T];
 
     fn deref(&self) -> &Self::Target {
         unsafe { self.as_slice(0, self.len) }
     }
 }
 
-impl DerefMut for VoodooBuffer {
+impl<T: Copy> DerefMut for VoodooBuffer<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         unsafe { self.as_slice_mut(0, self.len) }
     }
 }
 
-impl Index<usize> for VoodooBuffer {
-    type Output = u8;
+impl<T: Copy> Index<usize> for VoodooBuffer<T> {
+    type Output = T;
 
     fn index(&self, index: usize) -> &Self::Output {
         unsafe { &*self.addr.add(self.fast_mod(index)) }
     }
 }
 
-impl IndexMut<usize> for VoodooBuffer {
+impl<T: Copy> IndexMut<usize> for VoodooBuffer<T> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         unsafe { &mut *self.addr.add(self.fast_mod(index)) }
     }
 }
 
-impl Index<Range<usize>> for VoodooBuffer {
-    type Output = [u8];
+impl<T: Copy, R: BufferRange> Index<R> for VoodooBuffer<T> {
+    type Output = [T];
 
-    fn index(&self, index: Range<usize>) -> &Self::Output {
-        if index.start > index.end {
+    fn index(&self, index: R) -> &Self::Output {
+        let (start, end) = self.resolve_range(&index);
+        if start > end {
             return &[];
         }
 
-        let len = index.end - index.start;
+        let len = end - start;
         if len > self.len {
             panic!("out of bounds")
         }
 
-        unsafe { self.as_slice(self.fast_mod(index.start), len) }
+        unsafe { self.as_slice(self.fast_mod(start), len) }
     }
 }
 
-impl IndexMut<Range<usize>> for VoodooBuffer {
-    fn index_mut(&mut self, index: Range<usize>) -> &mut Self::Output {
-        if index.start > index.end {
+impl<T: Copy, R: BufferRange> IndexMut<R> for VoodooBuffer<T> {
+    fn index_mut(&mut self, index: R) -> &mut Self::Output {
+        let (start, end) = self.resolve_range(&index);
+        if start > end {
             return &mut [];
         }
 
-        let len = index.end - index.start;
+        let len = end - start;
         if len > self.len {
             panic!("out of bounds")
         }
 
-        unsafe { self.as_slice_mut(self.fast_mod(index.start), len) }
-    }
-}
-
-impl Index<RangeTo<usize>> for VoodooBuffer {
-    type Output = [u8];
-
-    fn index(&self, index: RangeTo<usize>) -> &Self::Output {
-        let start = index.end - self.len;
-        unsafe { self.as_slice(self.fast_mod(start), self.len) }
-    }
-}
-
-impl IndexMut<RangeTo<usize>> for VoodooBuffer {
-    fn index_mut(&mut self, index: RangeTo<usize>) -> &mut Self::Output {
-        let start = index.end - self.len;
-        unsafe { self.as_slice_mut(self.fast_mod(start), self.len) }
-    }
-}
-
-impl Index<RangeFrom<usize>> for VoodooBuffer {
-    type Output = [u8];
-
-    fn index(&self, index: RangeFrom<usize>) -> &Self::Output {
-        unsafe { self.as_slice(self.fast_mod(index.start), self.len) }
-    }
-}
-
-impl IndexMut<RangeFrom<usize>> for VoodooBuffer {
-    fn index_mut(&mut self, index: RangeFrom<usize>) -> &mut Self::Output {
-        unsafe { self.as_slice_mut(self.fast_mod(index.start), self.len) }
-    }
-}
-
-impl Index<RangeToInclusive<usize>> for VoodooBuffer {
-    type Output = [u8];
-
-    fn index(&self, index: RangeToInclusive<usize>) -> &Self::Output {
-        let start = index.end - self.len + 1;
-        unsafe { self.as_slice(self.fast_mod(start), self.len) }
-    }
-}
-
-impl IndexMut<RangeToInclusive<usize>> for VoodooBuffer {
-    fn index_mut(&mut self, index: RangeToInclusive<usize>) -> &mut Self::Output {
-        let start = index.end - self.len + 1;
-        unsafe { self.as_slice_mut(self.fast_mod(start), self.len) }
-    }
-}
-
-impl Index<RangeFull> for VoodooBuffer {
-    type Output = [u8];
-
-    fn index(&self, _: RangeFull) -> &Self::Output {
-        unsafe { self.as_slice(0, self.len) }
-    }
-}
-
-impl IndexMut<RangeFull> for VoodooBuffer {
-    fn index_mut(&mut self, _: RangeFull) -> &mut Self::Output {
-        unsafe { self.as_slice_mut(0, self.len) }
+        unsafe { self.as_slice_mut(self.fast_mod(start), len) }
     }
 }
 
@@ -238,13 +262,13 @@ mod tests {
 
     #[test]
     fn allocates_buffer() {
-        let buf = VoodooBuffer::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
         drop(buf);
     }
 
     #[test]
     fn requires_power_of_two() {
-        VoodooBuffer::new(INVALID_BUF_LEN_POW2)
+        VoodooBuffer::<u8>::new(INVALID_BUF_LEN_POW2)
             .map_err(|e| {
                 println!("{}", e.msg);
                 e
@@ -254,7 +278,18 @@ mod tests {
 
     #[test]
     fn requires_aligned_len() {
-        VoodooBuffer::new(INVALID_BUF_LEN_ALIGN)
+        VoodooBuffer::<u8>::new(INVALID_BUF_LEN_ALIGN)
+            .map_err(|e| {
+                println!("{}", e.msg);
+                e
+            })
+            .expect_err("should not allocate buffer");
+    }
+
+    #[test]
+    fn rejects_overflowing_byte_len() {
+        let len = 1usize << (usize::BITS - 1);
+        VoodooBuffer::<u128>::new(len)
             .map_err(|e| {
                 println!("{}", e.msg);
                 e
@@ -264,91 +299,116 @@ mod tests {
 
     #[test]
     fn writes_are_visible_wrap_around() {
-        let mut buf = VoodooBuffer::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let mut buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
         buf[0] = b'a';
         assert_eq!(buf[0], buf[VALID_BUF_LEN]);
     }
 
     #[test]
     fn deref_as_slice() {
-        let buf = VoodooBuffer::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
         let slice: &[u8] = &buf;
         assert_eq!(VALID_BUF_LEN, slice.len());
     }
 
     #[test]
     fn deref_mut_as_slice() {
-        let mut buf = VoodooBuffer::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let mut buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
         let slice: &mut [u8] = &mut buf;
         assert_eq!(VALID_BUF_LEN, slice.len());
     }
 
     #[test]
     fn closed_range() {
-        let buf = VoodooBuffer::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
         let slice = &buf[0..VALID_BUF_LEN];
         assert_eq!(VALID_BUF_LEN, slice.len());
     }
 
     #[test]
     fn closed_range_mut() {
-        let mut buf = VoodooBuffer::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let mut buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
         let slice = &mut buf[0..VALID_BUF_LEN];
         assert_eq!(VALID_BUF_LEN, slice.len());
     }
 
     #[test]
     fn range_to() {
-        let buf = VoodooBuffer::new(VALID_BUF_LEN).expect("should allocate buffer");
-        let slice = &buf[..VALID_BUF_LEN + 1];
-        assert_eq!(VALID_BUF_LEN, slice.len());
+        let buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let slice = &buf[..VALID_BUF_LEN / 2];
+        assert_eq!(VALID_BUF_LEN / 2, slice.len());
     }
 
     #[test]
     fn range_to_mut() {
-        let mut buf = VoodooBuffer::new(VALID_BUF_LEN).expect("should allocate buffer");
-        let slice = &mut buf[..VALID_BUF_LEN + 1];
-        assert_eq!(VALID_BUF_LEN, slice.len());
+        let mut buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let slice = &mut buf[..VALID_BUF_LEN / 2];
+        assert_eq!(VALID_BUF_LEN / 2, slice.len());
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn range_to_out_of_bounds_panics() {
+        let buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let _ = &buf[..VALID_BUF_LEN + 1];
     }
 
     #[test]
     fn range_from() {
-        let buf = VoodooBuffer::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
         let slice = &buf[1..];
-        assert_eq!(VALID_BUF_LEN, slice.len());
+        assert_eq!(VALID_BUF_LEN - 1, slice.len());
     }
 
     #[test]
     fn range_from_mut() {
-        let mut buf = VoodooBuffer::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let mut buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
         let slice = &mut buf[1..];
-        assert_eq!(VALID_BUF_LEN, slice.len());
+        assert_eq!(VALID_BUF_LEN - 1, slice.len());
     }
 
     #[test]
     fn range_to_inclusive() {
-        let buf = VoodooBuffer::new(VALID_BUF_LEN).expect("should allocate buffer");
-        let slice = &buf[..=VALID_BUF_LEN];
+        let buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let slice = &buf[..=VALID_BUF_LEN - 1];
         assert_eq!(VALID_BUF_LEN, slice.len());
     }
 
     #[test]
     fn range_to_inclusive_mut() {
-        let mut buf = VoodooBuffer::new(VALID_BUF_LEN).expect("should allocate buffer");
-        let slice = &mut buf[..=VALID_BUF_LEN];
+        let mut buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let slice = &mut buf[..=VALID_BUF_LEN - 1];
         assert_eq!(VALID_BUF_LEN, slice.len());
     }
 
     #[test]
     fn range_full() {
-        let buf = VoodooBuffer::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
         let slice = &buf[..];
         assert_eq!(VALID_BUF_LEN, slice.len());
     }
 
+    // `VALID_BUF_LEN` bytes' worth of `u32`s, so the byte length stays page aligned.
+    const VALID_TYPED_BUF_LEN: usize = VALID_BUF_LEN / 4;
+
+    #[test]
+    fn typed_buffer_writes_are_visible_wrap_around() {
+        let mut buf =
+            VoodooBuffer::<u32>::new(VALID_TYPED_BUF_LEN).expect("should allocate buffer");
+        buf[0] = 0xdead_beef;
+        assert_eq!(buf[0], buf[VALID_TYPED_BUF_LEN]);
+    }
+
+    #[test]
+    fn typed_buffer_from_slice() {
+        let data = [0xdead_beefu32; VALID_TYPED_BUF_LEN];
+        let buf = VoodooBuffer::from_slice(&data).expect("should allocate buffer");
+        assert_eq!(&buf[0..VALID_TYPED_BUF_LEN], &data[..]);
+    }
+
     #[test]
     fn range_full_mut() {
-        let mut buf = VoodooBuffer::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let mut buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
         let slice = &mut buf[..];
         assert_eq!(VALID_BUF_LEN, slice.len());
     }
@@ -0,0 +1,195 @@
+//! A wait-free single-producer/single-consumer byte pipe built on top of the
+//! double-mapped address space of a [`VoodooBuffer`].
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use crate::VoodooBuffer;
+
+struct Shared {
+    buf: VoodooBuffer,
+    head: AtomicUsize,
+    tail: AtomicUsize,
+}
+
+// Safety: `Shared`'s raw pointer (inside `buf`) is only ever dereferenced through
+// `as_slice` over the readable region `[tail, head)` (consumer) and
+// `as_slice_mut_shared` over the writable region `[head, tail + len)` (producer), which
+// the `Acquire`/`Release` atomics keep disjoint.
+// That's exactly what makes it sound for a `Producer` and `Consumer` built from the same
+// `split()` call to live on different threads.
+unsafe impl Send for Shared {}
+unsafe impl Sync for Shared {}
+
+/// The writing half of a [`VoodooBuffer`] split via [`VoodooBuffer::split`].
+pub struct Producer {
+    shared: Arc<Shared>,
+}
+
+/// The reading half of a [`VoodooBuffer`] split via [`VoodooBuffer::split`].
+pub struct Consumer {
+    shared: Arc<Shared>,
+}
+
+impl VoodooBuffer {
+    /// Splits the buffer into a wait-free single-producer/single-consumer byte pipe.
+    ///
+    /// `head` and `tail` grow monotonically and are never wrapped, so the readable and
+    /// writable regions handed out by [`Consumer::readable_slice`] and
+    /// [`Producer::writable_slice`] are always a single contiguous slice, even when they
+    /// straddle the wrap point of the underlying mirror mapping.
+    pub fn split(self) -> (Producer, Consumer) {
+        let shared = Arc::new(Shared {
+            buf: self,
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        });
+
+        (
+            Producer {
+                shared: shared.clone(),
+            },
+            Consumer { shared },
+        )
+    }
+}
+
+impl Producer {
+    /// Number of bytes that can currently be written without overrunning the consumer.
+    pub fn writable_len(&self) -> usize {
+        let head = self.shared.head.load(Ordering::Relaxed);
+        let tail = self.shared.tail.load(Ordering::Acquire);
+        self.shared.buf.len() - (head - tail)
+    }
+
+    /// Returns the contiguous writable region at the current write position.
+    pub fn writable_slice(&mut self) -> &mut [u8] {
+        let head = self.shared.head.load(Ordering::Relaxed);
+        let tail = self.shared.tail.load(Ordering::Acquire);
+        let len = self.shared.buf.len() - (head - tail);
+        let offset = self.shared.buf.fast_mod(head);
+
+        // Safety: only the producer ever writes, and only to the region between `head`
+        // and `tail + len`, which the consumer never touches.
+        unsafe { self.shared.buf.as_slice_mut_shared(offset, len) }
+    }
+
+    /// Commits `n` bytes written via [`writable_slice`](Self::writable_slice), making
+    /// them visible to the consumer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than [`writable_len`](Self::writable_len).
+    pub fn commit(&mut self, n: usize) {
+        assert!(
+            n <= self.writable_len(),
+            "commit({}) exceeds writable length {}",
+            n,
+            self.writable_len()
+        );
+        self.shared.head.fetch_add(n, Ordering::Release);
+    }
+}
+
+impl Consumer {
+    /// Number of bytes currently available to read.
+    pub fn readable_len(&self) -> usize {
+        let tail = self.shared.tail.load(Ordering::Relaxed);
+        let head = self.shared.head.load(Ordering::Acquire);
+        head - tail
+    }
+
+    /// Returns the contiguous readable region at the current read position.
+    pub fn readable_slice(&self) -> &[u8] {
+        let tail = self.shared.tail.load(Ordering::Relaxed);
+        let head = self.shared.head.load(Ordering::Acquire);
+        let offset = self.shared.buf.fast_mod(tail);
+
+        // Safety: only the consumer ever reads, and only from the region between `tail`
+        // and `head`, which the producer never touches.
+        unsafe { self.shared.buf.as_slice(offset, head - tail) }
+    }
+
+    /// Consumes `n` bytes returned by [`readable_slice`](Self::readable_slice), freeing
+    /// that space for the producer to write into.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than [`readable_len`](Self::readable_len).
+    pub fn consume(&mut self, n: usize) {
+        assert!(
+            n <= self.readable_len(),
+            "consume({}) exceeds readable length {}",
+            n,
+            self.readable_len()
+        );
+        self.shared.tail.fetch_add(n, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID_BUF_LEN: usize = 1 << 16;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn producer_and_consumer_are_send_sync() {
+        assert_send_sync::<Producer>();
+        assert_send_sync::<Consumer>();
+    }
+
+    #[test]
+    fn split_round_trips_bytes() {
+        let buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let (mut producer, mut consumer) = buf.split();
+
+        let written = producer.writable_slice();
+        written[..5].copy_from_slice(b"hello");
+        producer.commit(5);
+
+        let readable = consumer.readable_slice();
+        assert_eq!(&readable[..5], b"hello");
+        consumer.consume(5);
+
+        assert_eq!(consumer.readable_len(), 0);
+        assert_eq!(producer.writable_len(), VALID_BUF_LEN);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds writable length")]
+    fn commit_past_writable_len_panics() {
+        let buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let (mut producer, _consumer) = buf.split();
+        producer.commit(VALID_BUF_LEN + 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds readable length")]
+    fn consume_past_readable_len_panics() {
+        let buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let (_producer, mut consumer) = buf.split();
+        consumer.consume(1);
+    }
+
+    #[test]
+    fn split_wraps_contiguously() {
+        let buf = VoodooBuffer::<u8>::new(VALID_BUF_LEN).expect("should allocate buffer");
+        let (mut producer, mut consumer) = buf.split();
+
+        producer.commit(VALID_BUF_LEN - 2);
+        consumer.consume(VALID_BUF_LEN - 2);
+
+        let written = producer.writable_slice();
+        assert_eq!(written.len(), VALID_BUF_LEN);
+        written[..4].copy_from_slice(b"wrap");
+        producer.commit(4);
+
+        let readable = consumer.readable_slice();
+        assert_eq!(&readable[..4], b"wrap");
+    }
+}